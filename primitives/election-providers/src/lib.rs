@@ -42,9 +42,8 @@
 //! > It could also be possible that a third party pallet (C), provides the data of election to an
 //! > election provider (B), which then passes the election result to another pallet (A).
 //!
-//! Note that the [`ElectionProvider`] does not have a hard tie to the [`ElectionDataProvider`],
-//! rather the link must be created by other means during implementation (i.e. an associated type in
-//! `Config` trait the case of FRAME pallets).
+//! Note that the [`ElectionProvider`] is hard-linked, via [`ElectionProviderBase::DataProvider`],
+//! to the [`ElectionDataProvider`] that feeds it.
 //!
 //! ## Election Types
 //!
@@ -78,6 +77,8 @@
 //! ```rust
 //! # use sp_election_providers::*;
 //! # use sp_npos_elections::Support;
+//! # use frame_support::weights::Weight;
+//! # use frame_support::traits::ConstU32;
 //!
 //! type AccountId = u64;
 //!	type Balance = u64;
@@ -88,20 +89,41 @@
 //!
 //! 	pub trait Config {
 //! 		type AccountId;
-//! 		type ElectionProvider: ElectionProvider<Self::AccountId>;
+//! 		type ElectionProvider: ElectionProvider<Self::AccountId, BlockNumber>;
 //! 	}
 //!
 //!		pub struct Module<T: Config>(std::marker::PhantomData<T>);
 //!
+//!		/// The snapshot has not been built yet.
+//!		#[derive(Debug, PartialEq, Eq)]
+//!		pub struct SnapshotNotReady;
+//!
 //!		impl<T: Config> ElectionDataProvider<AccountId, BlockNumber> for Module<T> {
-//!			fn desired_targets() -> u32 {
-//!				1
+//!			type Error = SnapshotNotReady;
+//!			type MaxVotesPerVoter = ConstU32<16>;
+//!
+//!			fn desired_targets() -> Result<u32, Self::Error> {
+//!				Ok(1)
+//!			}
+//!			fn electing_voters(_bounds: DataProviderBounds) -> Result<Vec<(AccountId, VoteWeight, Vec<AccountId>)>, Self::Error> {
+//!				Ok(Default::default())
+//!			}
+//!			fn electable_targets(bounds: DataProviderBounds) -> Result<Vec<AccountId>, Self::Error> {
+//!				// Each `AccountId` (a `u64`) SCALE-encodes to 8 bytes.
+//!				let mut targets = Vec::new();
+//!				for target in vec![10, 20, 30] {
+//!					if bounds.exhausted(targets.len(), targets.len() * 8, 8) {
+//!						break;
+//!					}
+//!					targets.push(target);
+//!				}
+//!				Ok(targets)
 //!			}
-//!			fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
+//!			fn targets_weight(_count: u32) -> Weight {
 //!				Default::default()
 //!			}
-//!			fn targets() -> Vec<AccountId> {
-//!				vec![10, 20, 30]
+//!			fn voters_weight(_count: u32, _edges: u32) -> Weight {
+//!				Default::default()
 //!			}
 //!			fn feasibility_check_assignment<P: PerThing>(
 //!				who: &AccountId,
@@ -118,21 +140,43 @@
 //!
 //! mod election_provider {
 //! 	use super::*;
+//! 	use super::data_provider::SnapshotNotReady;
 //!
 //! 	pub struct SomeElectionProvider<T: Config>(std::marker::PhantomData<T>);
 //!
 //! 	pub trait Config {
-//! 		type DataProvider: ElectionDataProvider<AccountId, BlockNumber>;
+//! 		type DataProvider: ElectionDataProvider<AccountId, BlockNumber, Error = SnapshotNotReady>;
 //! 	}
 //!
-//! 	impl<T: Config> ElectionProvider<AccountId> for SomeElectionProvider<T> {
-//! 		type Error = ();
+//! 	/// This provider's own error, distinct from any error bubbled up from its data provider.
+//! 	#[derive(Debug)]
+//! 	pub enum ElectError {
+//! 		/// The data provider could not serve a snapshot.
+//! 		DataProvider(SnapshotNotReady),
+//! 		/// The data provider returned no targets at all.
+//! 		NoTargets,
+//! 	}
 //!
+//! 	impl From<SnapshotNotReady> for ElectError {
+//! 		fn from(e: SnapshotNotReady) -> Self {
+//! 			ElectError::DataProvider(e)
+//! 		}
+//! 	}
+//!
+//! 	impl<T: Config> ElectionProviderBase<AccountId, BlockNumber> for SomeElectionProvider<T> {
+//! 		type DataProvider = T::DataProvider;
+//! 		type Error = ElectError;
+//! 	}
+//!
+//! 	impl<T: Config> ElectionProvider<AccountId, BlockNumber> for SomeElectionProvider<T> {
 //! 		fn elect<P: PerThing128>() -> Result<Supports<AccountId>, Self::Error> {
-//! 			T::DataProvider::targets()
+//! 			// The `?` here relies on `Self::Error: From<DataProvider::Error>` to distinguish a
+//! 			// failed fetch (`ElectError::DataProvider`) from "fetched fine, but infeasible"
+//! 			// (`ElectError::NoTargets`).
+//! 			Self::DataProvider::electable_targets(DataProviderBounds::default())?
 //! 				.first()
 //! 				.map(|winner| vec![(*winner, Support::default())])
-//! 				.ok_or(())
+//! 				.ok_or(ElectError::NoTargets)
 //! 		}
 //! 		fn ongoing() -> bool {
 //!				false
@@ -166,27 +210,155 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use sp_std::prelude::*;
+use frame_support::{traits::Get, weights::Weight};
 
 /// Re-export some type as they are used in the interface.
 pub use sp_npos_elections::{CompactSolution, ExtendedBalance, PerThing128, Supports, VoteWeight};
 pub use sp_arithmetic::PerThing;
 
+/// A bound on the number of items a [`ElectionDataProvider`] snapshot query is allowed to return.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CountBound(pub u32);
+
+/// A bound on the total SCALE-encoded byte length of the items a [`ElectionDataProvider`]
+/// snapshot query is allowed to return.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SizeBound(pub u32);
+
+/// Bounds placed on a single call to a length-bounded [`ElectionDataProvider`] snapshot query.
+///
+/// A `None` in either field means "no bound on this dimension". When both fields are `Some`, the
+/// implementor must stop adding items to the returned vector as soon as *either* bound would be
+/// exceeded, i.e. both bounds must hold simultaneously over the returned prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DataProviderBounds {
+	/// The maximum number of items to return, if any.
+	pub count: Option<CountBound>,
+	/// The maximum total SCALE-encoded byte length of the returned items, if any.
+	pub size: Option<SizeBound>,
+}
+
+impl DataProviderBounds {
+	/// Whether or not adding one more item, whose own SCALE-encoded length is `candidate_size`,
+	/// would push `self` over its bounds, given that `count` items totalling `size` encoded bytes
+	/// have already been added.
+	///
+	/// This is meant to be called once per candidate item, *before* it is pushed into the result,
+	/// so that the caller can stop as soon as this returns `true`. Note that `candidate_size` is
+	/// the encoded length of the candidate alone, not the prospective running total -- this
+	/// method adds it to `size` itself.
+	pub fn exhausted(&self, count: usize, size: usize, candidate_size: usize) -> bool {
+		let count_exhausted = self.count.map_or(false, |CountBound(max)| count as u32 >= max);
+		let size_exhausted =
+			self.size.map_or(false, |SizeBound(max)| (size + candidate_size) as u32 > max);
+		count_exhausted || size_exhausted
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn count_bound_stops_at_the_limit() {
+		let bounds = DataProviderBounds { count: Some(CountBound(2)), size: None };
+		assert!(!bounds.exhausted(0, 0, 10));
+		assert!(!bounds.exhausted(1, 10, 10));
+		assert!(bounds.exhausted(2, 20, 10));
+	}
+
+	#[test]
+	fn size_bound_rejects_a_candidate_that_alone_would_overshoot() {
+		let bounds = DataProviderBounds { count: None, size: Some(SizeBound(100)) };
+		// Nothing added yet, but the next candidate alone is far bigger than the whole budget.
+		assert!(bounds.exhausted(0, 0, 5_000));
+		// A candidate that exactly fills the remaining budget is still allowed.
+		assert!(!bounds.exhausted(0, 0, 100));
+		// One byte over should no longer fit.
+		assert!(bounds.exhausted(0, 0, 101));
+		// Once some size has accumulated, a candidate that only fits the remainder is fine.
+		assert!(!bounds.exhausted(1, 60, 40));
+		assert!(bounds.exhausted(1, 60, 41));
+	}
+
+	#[test]
+	fn both_bounds_must_hold() {
+		let bounds = DataProviderBounds { count: Some(CountBound(10)), size: Some(SizeBound(50)) };
+		// Plenty of room on count, but size alone is exceeded.
+		assert!(bounds.exhausted(1, 10, 41));
+		// Plenty of room on size, but count alone is exceeded.
+		assert!(bounds.exhausted(10, 10, 1));
+	}
+}
+
 /// Something that can provide the data to something else that implements [`ElectionProvider`].
 ///
 /// The underlying purpose of this is to provide auxillary data to stateful election providers. For
 /// example, multi-block election provider needs to know the voters/targets list well in advance and
 /// before a call to [`ElectionProvider::elect`].
+///
+/// ## Self-weighing
+///
+/// [`Self::electable_targets`] and [`Self::electing_voters`] are self-weighing: because their cost
+/// is data-dependent, the implementor -- which, unlike this crate, is free to depend on
+/// `frame_system` -- must, before returning, register the weight it actually consumed via
+/// `frame_system::register_extra_weight_unchecked`. Respecting `bounds` is what keeps that
+/// registered weight within the block budget.
 pub trait ElectionDataProvider<AccountId, BlockNumber> {
-	/// All possible targets for the election, i.e. the candidates.
-	fn targets() -> Vec<AccountId>;
+	/// The error type returned by this trait's snapshot methods.
+	///
+	/// This allows an implementor to signal that it cannot currently serve a consistent
+	/// snapshot (e.g. mid-migration, locked storage, or a stateful provider whose snapshot has
+	/// not been built yet), rather than having to fall back to misleading empty or partial data.
+	type Error;
+
+	/// The maximum number of votes (i.e. nominated targets) that any single voter returned from
+	/// [`Self::electing_voters`] may have.
+	///
+	/// This bounds the width, as opposed to the length, of the voter snapshot, which in turn lets
+	/// a caller size its per-voter compact/solution arrays statically.
+	type MaxVotesPerVoter: Get<u32>;
+
+	/// All possible targets for the election, i.e. the candidates, up to the given `bounds`.
+	///
+	/// The implementor must truncate the returned vector so that it never exceeds `bounds`,
+	/// stopping as soon as adding one more target would push it over either the count or the
+	/// encoded-size limit. A truncated result is simply a valid prefix of the full target set; it
+	/// is up to the caller to page through the rest, if needed.
+	///
+	/// Self-weighing: see the note on [`Self::targets_weight`] for the weight to register.
+	fn electable_targets(bounds: DataProviderBounds) -> Result<Vec<AccountId>, Self::Error>;
 
-	/// All possible voters for the election.
+	/// All possible voters for the election, up to the given `bounds`.
 	///
 	/// Note that if a notion of self-vote exists, it should be represented here.
-	fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)>;
+	///
+	/// The implementor must truncate the returned vector so that it never exceeds `bounds`,
+	/// stopping as soon as adding one more voter would push it over either the count or the
+	/// encoded-size limit. In addition, every returned voter's nomination vector must itself be
+	/// truncated to at most [`Self::MaxVotesPerVoter`] targets, deterministically keeping the
+	/// first `MaxVotesPerVoter` targets by the provider's own ordering if a voter nominated more.
+	///
+	/// Self-weighing: see the note on [`Self::voters_weight`] for the weight to register.
+	fn electing_voters(
+		bounds: DataProviderBounds,
+	) -> Result<Vec<(AccountId, VoteWeight, Vec<AccountId>)>, Self::Error>;
 
 	/// The number of targets to elect.
-	fn desired_targets() -> u32;
+	fn desired_targets() -> Result<u32, Self::Error>;
+
+	/// The weight that [`Self::electable_targets`] would consume if it returned `count` targets.
+	///
+	/// This is both what a caller can use to pre-estimate the cost of a call, and what
+	/// [`Self::electable_targets`] must itself register, per the "Self-weighing" section above.
+	fn targets_weight(count: u32) -> Weight;
+
+	/// The weight that [`Self::electing_voters`] would consume if it returned `count` voters whose
+	/// nomination edges sum to `edges` in total.
+	///
+	/// This is both what a caller can use to pre-estimate the cost of a call, and what
+	/// [`Self::electing_voters`] must itself register, per the "Self-weighing" section above.
+	fn voters_weight(count: u32, edges: u32) -> Weight;
 
 	/// Check the feasibility of a single assignment for the underlying `ElectionProvider`. In other
 	/// words, check if `who` having a weight distribution described as `distribution` is correct or
@@ -209,21 +381,42 @@ pub trait ElectionDataProvider<AccountId, BlockNumber> {
 	fn next_election_prediction(now: BlockNumber) -> BlockNumber;
 }
 
-/// Something that can compute the result of an election and pass it back to the caller.
+/// The base trait shared by all [`ElectionProvider`]s, carrying the hard link to the
+/// [`ElectionDataProvider`] that feeds it.
 ///
-/// This trait only provides an interface to _request_ an election, i.e.
-/// [`ElectionProvider::elect`]. That data required for the election need to be passed to the
-/// implemented of this trait through some other way. One example of such is the
-/// [`ElectionDataProvider`] traits.
-pub trait ElectionProvider<AccountId> {
+/// Previously an [`ElectionProvider`] had "no hard tie" to an [`ElectionDataProvider`] and the
+/// link had to be wired up manually per-pallet (e.g. by re-threading a generic `T::DataProvider`
+/// through the runtime `Config`). Requiring this trait instead gives a single canonical place to
+/// express that dependency, so [`ElectionProvider::elect`] can reach `Self::DataProvider` directly.
+pub trait ElectionProviderBase<AccountId, BlockNumber> {
+	/// The data provider that this election provider is hard-linked to.
+	type DataProvider: ElectionDataProvider<AccountId, BlockNumber>;
+
 	/// The error type that is returned by the provider.
-	type Error;
+	///
+	/// This must be able to represent an error coming from `Self::DataProvider`, so that a caller
+	/// can tell "the data could not be fetched" apart from "the fetched data was infeasible".
+	type Error: From<<Self::DataProvider as ElectionDataProvider<AccountId, BlockNumber>>::Error>;
+}
 
+/// Something that can compute the result of an election and pass it back to the caller.
+///
+/// This trait only provides an interface to _request_ an election, i.e.
+/// [`ElectionProvider::elect`]. The data required for the election is sourced from
+/// [`ElectionProviderBase::DataProvider`].
+///
+/// This trait now takes `BlockNumber` as a second generic parameter, in addition to `AccountId`,
+/// purely to be able to require [`ElectionProviderBase<AccountId, BlockNumber>`] as a supertrait
+/// (the hard-linked [`ElectionDataProvider`] is itself generic over both). Every existing
+/// implementor must add a `BlockNumber` parameter to its `impl` block accordingly.
+pub trait ElectionProvider<AccountId, BlockNumber>: ElectionProviderBase<AccountId, BlockNumber> {
 	/// Elect a new set of winners.
 	///
 	/// The result is returned in a target major format, namely as vector of  supports.
 	///
 	/// The implementation should, if possible, use the accuracy `P` to compute the election result.
+	/// `Self::DataProvider::MaxVotesPerVoter` is reachable through the hard link to the data
+	/// provider, allowing a compact/solution type to size its per-voter arrays statically.
 	fn elect<P: PerThing128>() -> Result<Supports<AccountId>, Self::Error>;
 
 	/// Returns true if an election is still ongoing.